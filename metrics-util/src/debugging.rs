@@ -24,6 +24,156 @@ impl Snapshot {
             .map(|(k, (unit, desc, value))| (k, unit, desc, value))
             .collect::<Vec<_>>()
     }
+
+    /// Renders this snapshot in the Prometheus text exposition format.
+    ///
+    /// Metric names are sanitized to the `[a-zA-Z0-9_:]` character set required by Prometheus,
+    /// and a single `# HELP`/`# TYPE` pair is emitted per distinct name, even when multiple keys
+    /// in the snapshot share that name under different label sets. Histograms are rendered as a
+    /// `summary`: a `_sum`, a `_count`, and `quantile="0.5|0.9|0.99"` lines. Entries are sorted by
+    /// name and then by label set so the output is stable across runs, since `self.0` is a
+    /// `HashMap` with no inherent order.
+    pub fn render_prometheus(&self) -> String {
+        let mut entries: Vec<(String, String, &CompositeKey, &Option<&'static str>, &DebugValue)> = self
+            .0
+            .iter()
+            .map(|(key, (_unit, desc, value))| {
+                let name = sanitize_metric_name(key.key().name());
+                let labels =
+                    render_label_string(key.key().labels().map(|l| (l.key(), l.value().to_string())));
+                (name, labels, key, desc, value)
+            })
+            .collect();
+        entries.sort_by(|(a_name, a_labels, ..), (b_name, b_labels, ..)| {
+            (a_name, a_labels).cmp(&(b_name, b_labels))
+        });
+
+        let mut output = String::new();
+        let mut current_name: Option<&str> = None;
+        for (name, labels, key, desc, value) in &entries {
+            if current_name != Some(name.as_str()) {
+                if let Some(desc) = desc {
+                    output.push_str(&format!("# HELP {} {}\n", name, desc));
+                }
+
+                let type_name = match key.kind() {
+                    MetricKind::Counter => "counter",
+                    MetricKind::Gauge => "gauge",
+                    // A summary, not a `histogram`: we emit `_sum`/`_count`/`quantile` series,
+                    // not `_bucket{le=...}` series, and Prometheus only accepts a `quantile`
+                    // label on a `summary`.
+                    MetricKind::Histogram => "summary",
+                };
+                output.push_str(&format!("# TYPE {} {}\n", name, type_name));
+
+                current_name = Some(name.as_str());
+            }
+
+            match value {
+                DebugValue::Counter(v) => output.push_str(&format!("{}{} {}\n", name, labels, v)),
+                DebugValue::Gauge(v) => output.push_str(&format!(
+                    "{}{} {}\n",
+                    name,
+                    labels,
+                    format_prometheus_f64(v.into_inner())
+                )),
+                DebugValue::Histogram(_) => {
+                    render_histogram_as_summary(&mut output, name, key.key(), value)
+                }
+            }
+        }
+
+        output
+    }
+}
+
+fn render_histogram_as_summary(output: &mut String, name: &str, key: &Key, value: &DebugValue) {
+    let (Some(sum), Some(count)) = (value.sum(), value.count()) else {
+        return;
+    };
+    if count == 0 {
+        return;
+    }
+
+    let labels = render_label_string(key.labels().map(|l| (l.key(), l.value().to_string())));
+    output.push_str(&format!("{}_sum{} {}\n", name, labels, format_prometheus_f64(sum)));
+    output.push_str(&format!("{}_count{} {}\n", name, labels, count));
+
+    for (q, v) in value.quantiles(&[0.5, 0.9, 0.99]).unwrap_or_default() {
+        let quantile_labels = render_label_string(
+            key.labels()
+                .map(|l| (l.key(), l.value().to_string()))
+                .chain(std::iter::once(("quantile", q.to_string()))),
+        );
+        output.push_str(&format!("{}{} {}\n", name, quantile_labels, format_prometheus_f64(v)));
+    }
+}
+
+/// Formats an `f64` the way Prometheus text exposition expects: `Display`'s `inf`/`-inf` aren't
+/// valid there, which requires `+Inf`/`-Inf`; `NaN` already prints as Prometheus expects.
+fn format_prometheus_f64(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_string()
+    } else if v == f64::INFINITY {
+        "+Inf".to_string()
+    } else if v == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        v.to_string()
+    }
+}
+
+/// Computes the `q`th quantile of an already-sorted slice via nearest-rank interpolation.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+
+    let rank = q * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (rank - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+fn render_label_string<'a, I>(pairs: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, String)>,
+{
+    let mut rendered = String::new();
+    let mut first = true;
+    for (k, v) in pairs {
+        rendered.push(if first { '{' } else { ',' });
+        first = false;
+        rendered.push_str(k);
+        rendered.push_str("=\"");
+        rendered.push_str(&escape_label_value(&v));
+        rendered.push('"');
+    }
+    if !first {
+        rendered.push('}');
+    }
+
+    rendered
+}
+
+fn escape_label_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            other => escaped.push(other),
+        }
+    }
+
+    escaped
+}
+
+fn sanitize_metric_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == ':' { c } else { '_' })
+        .collect()
 }
 
 /// A point-in-time value for a metric exposing raw values.
@@ -37,7 +187,59 @@ pub enum DebugValue {
     Histogram(Vec<OrderedFloat<f64>>),
 }
 
+impl DebugValue {
+    /// Computes the given quantiles over this histogram's samples.
+    ///
+    /// Each quantile `q` is computed over a sorted copy of the samples via nearest-rank linear
+    /// interpolation: `rank = q * (n - 1)`, interpolating between the values at `floor(rank)` and
+    /// `ceil(rank)`. A histogram with a single sample returns that sample for every quantile.
+    ///
+    /// Returns `None` if this is not a [`DebugValue::Histogram`], or if it has no samples.
+    pub fn quantiles(&self, quantiles: &[f64]) -> Option<Vec<(f64, f64)>> {
+        let sorted = self.sorted_samples()?;
+        if sorted.is_empty() {
+            return None;
+        }
+
+        Some(quantiles.iter().map(|&q| (q, quantile(&sorted, q))).collect())
+    }
+
+    /// The sum of this histogram's samples, or `None` if this is not a histogram.
+    pub fn sum(&self) -> Option<f64> {
+        self.sorted_samples().map(|samples| samples.iter().sum())
+    }
+
+    /// The number of samples in this histogram, or `None` if this is not a histogram.
+    pub fn count(&self) -> Option<usize> {
+        self.sorted_samples().map(|samples| samples.len())
+    }
+
+    /// The smallest sample in this histogram, or `None` if this is not a histogram or has no
+    /// samples.
+    pub fn min(&self) -> Option<f64> {
+        self.sorted_samples().and_then(|samples| samples.first().copied())
+    }
+
+    /// The largest sample in this histogram, or `None` if this is not a histogram or has no
+    /// samples.
+    pub fn max(&self) -> Option<f64> {
+        self.sorted_samples().and_then(|samples| samples.last().copied())
+    }
+
+    fn sorted_samples(&self) -> Option<Vec<f64>> {
+        match self {
+            DebugValue::Histogram(values) => {
+                let mut sorted = values.clone();
+                sorted.sort();
+                Some(sorted.into_iter().map(OrderedFloat::into_inner).collect())
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Captures point-in-time snapshots of `DebuggingRecorder`.
+#[derive(Clone)]
 pub struct Snapshotter {
     registry: Arc<Registry>,
     metrics: Arc<Mutex<IndexMap<CompositeKey, (Option<Unit>, Option<&'static str>)>>>,
@@ -76,6 +278,15 @@ impl Snapshotter {
 
         Snapshot(snapshot)
     }
+
+    /// Takes a snapshot of the recorder and renders it in the Prometheus text exposition format.
+    ///
+    /// This is a convenience wrapper over [`snapshot`][Self::snapshot] and
+    /// [`Snapshot::render_prometheus`], letting the `DebuggingRecorder` be scraped directly
+    /// without pulling in a separate exporter crate.
+    pub fn render_prometheus(&self) -> String {
+        self.snapshot().render_prometheus()
+    }
 }
 
 /// A simplistic recorder that can be installed and used for debugging or testing.
@@ -0,0 +1,86 @@
+//! An optional HTTP scrape server for [`Snapshotter`].
+//!
+//! Gated behind the `http-scrape` feature so that default builds of `metrics-util` don't pick up
+//! `hyper`/`tokio`. This exists for embedding applications that just want a working Prometheus
+//! scrape endpoint without wiring in a dedicated exporter crate.
+#![cfg(feature = "http-scrape")]
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Response, Server, StatusCode};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use crate::debugging::Snapshotter;
+
+/// A handle to a server spawned by [`Snapshotter::serve`].
+///
+/// Dropping this handle leaves the server running in the background; call
+/// [`ScrapeServerHandle::shutdown`] to stop it.
+pub struct ScrapeServerHandle {
+    task: JoinHandle<()>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl ScrapeServerHandle {
+    /// Stops the server and waits for it to finish shutting down.
+    pub async fn shutdown(mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+
+        let _ = self.task.await;
+    }
+}
+
+impl Snapshotter {
+    /// Spawns a minimal HTTP server exposing `GET /metrics` as a Prometheus scrape endpoint.
+    ///
+    /// Every request to `/metrics` takes a fresh [`snapshot`][Self::snapshot] and renders it via
+    /// [`Snapshot::render_prometheus`][crate::debugging::Snapshot::render_prometheus]. The
+    /// returned handle can be used to cancel the server; dropping it without calling
+    /// [`ScrapeServerHandle::shutdown`] leaves the server running in the background.
+    pub fn serve(&self, addr: SocketAddr) -> Result<ScrapeServerHandle, hyper::Error> {
+        let snapshotter = self.clone();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let make_svc = make_service_fn(move |_conn| {
+            let snapshotter = snapshotter.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| {
+                    let snapshotter = snapshotter.clone();
+                    async move {
+                        let response = if req.method() == Method::GET && req.uri().path() == "/metrics" {
+                            Response::builder()
+                                .header("Content-Type", "text/plain; version=0.0.4")
+                                .body(Body::from(snapshotter.render_prometheus()))
+                                .expect("response should always be valid")
+                        } else {
+                            Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::empty())
+                                .expect("response should always be valid")
+                        };
+
+                        Ok::<_, Infallible>(response)
+                    }
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&addr)?.serve(make_svc).with_graceful_shutdown(async {
+            // A dropped sender means the handle was dropped without `shutdown`: keep serving.
+            if shutdown_rx.await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        });
+
+        let task = tokio::spawn(async move {
+            let _ = server.await;
+        });
+
+        Ok(ScrapeServerHandle { task, shutdown: Some(shutdown_tx) })
+    }
+}
@@ -0,0 +1,4 @@
+pub mod debugging;
+
+#[cfg(feature = "http-scrape")]
+pub mod http_scrape;
@@ -5,10 +5,10 @@ use self::cell::{RecorderOnceCell, RecorderVariant};
 use crate::{Counter, Gauge, Histogram, Key, KeyName, Metadata, SharedString, Unit};
 
 mod cell {
-    use super::{Recorder, SetRecorderError};
+    use super::{Recorder, SetRecorderError, SetRecorderErrorReason};
     use std::{
-        cell::UnsafeCell,
-        sync::atomic::{AtomicUsize, Ordering},
+        ptr,
+        sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering},
     };
 
     /// The recorder is uninitialized.
@@ -37,7 +37,12 @@ mod cell {
         pub fn into_recorder_ref(self) -> &'static dyn Recorder {
             match self {
                 Self::Static(recorder) => recorder,
-                Self::Boxed(recorder) => recorder,
+                Self::Boxed(recorder) => {
+                    // We're handing out the leaked `'static` reference ourselves now, so make
+                    // sure our `Drop` impl doesn't also free it out from under the caller.
+                    std::mem::forget(self);
+                    recorder
+                }
             }
         }
     }
@@ -55,14 +60,26 @@ mod cell {
 
     /// An specialized version of `OnceCell` for `Recorder`.
     pub struct RecorderOnceCell {
-        recorder: UnsafeCell<Option<&'static dyn Recorder>>,
+        // Points at a leaked `Box<&'static dyn Recorder>`, i.e. a thin pointer to the (fat)
+        // recorder reference. Going through this indirection lets us swap the recorder with a
+        // single atomic pointer store, so `try_load` never has to tear a multi-word fat pointer
+        // apart from a concurrent writer.
+        recorder: AtomicPtr<&'static dyn Recorder>,
         state: AtomicUsize,
+        // Serializes concurrent `upgrade` calls against each other. `state` intentionally stays
+        // `INITIALIZED` for the whole swap so that `try_load` keeps reading `recorder` instead of
+        // reporting a spurious gap; this flag is what keeps two upgrades from racing instead.
+        upgrading: AtomicBool,
     }
 
     impl RecorderOnceCell {
         /// Creates an uninitialized `RecorderOnceCell`.
         pub const fn new() -> Self {
-            Self { recorder: UnsafeCell::new(None), state: AtomicUsize::new(UNINITIALIZED) }
+            Self {
+                recorder: AtomicPtr::new(ptr::null_mut()),
+                state: AtomicUsize::new(UNINITIALIZED),
+                upgrading: AtomicBool::new(false),
+            }
         }
 
         pub fn set(&self, variant: RecorderVariant) -> Result<(), SetRecorderError> {
@@ -75,18 +92,68 @@ mod cell {
                 Ordering::Relaxed,
             ) {
                 Ok(UNINITIALIZED) => {
-                    unsafe {
-                        // SAFETY: Access is unique because we can only be here if we won the race
-                        // to transition from `UNINITIALIZED` to `INITIALIZING` above.
-                        self.recorder.get().write(Some(variant.into_recorder_ref()));
-                    }
+                    let ptr = Box::into_raw(Box::new(variant.into_recorder_ref()));
+                    self.recorder.store(ptr, Ordering::Release);
 
                     // Mark the recorder as initialized, which will make it visible to readers.
                     self.state.store(INITIALIZED, Ordering::Release);
                     Ok(())
                 }
-                _ => Err(SetRecorderError(())),
+                _ => Err(SetRecorderError(SetRecorderErrorReason::AlreadySet)),
+            }
+        }
+
+        /// Atomically replaces the currently installed recorder with one built from it.
+        ///
+        /// `f` is handed the currently installed recorder and returns the `Box<dyn Recorder>`
+        /// that should replace it, e.g. to layer an exporter on top of a recorder that was
+        /// installed at startup. The swap happens via a single atomic pointer store, so
+        /// concurrent [`try_load`][Self::try_load] readers always observe either the old or the
+        /// new recorder, never a gap or a torn reference.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if no recorder has been installed yet, since there is nothing to
+        /// upgrade, or if another `upgrade` call is already in progress.
+        pub fn upgrade(
+            &self,
+            f: impl FnOnce(&'static dyn Recorder) -> Box<dyn Recorder>,
+        ) -> Result<(), SetRecorderError> {
+            if self.upgrading.swap(true, Ordering::AcqRel) {
+                return Err(SetRecorderError(SetRecorderErrorReason::UpgradeInProgress));
             }
+
+            let outcome = match self.state.load(Ordering::Acquire) {
+                INITIALIZED => {
+                    let current_ptr = self.recorder.load(Ordering::Acquire);
+                    debug_assert!(
+                        !current_ptr.is_null(),
+                        "recorder pointer should be set when initialized"
+                    );
+
+                    // SAFETY: `current_ptr` was produced by a prior `Box::into_raw` in `set` or
+                    // `upgrade`, and we never free it (see the `store` below), so it's always
+                    // valid to dereference.
+                    let current = unsafe { *current_ptr };
+
+                    let replacement = RecorderVariant::from_boxed(f(current)).into_recorder_ref();
+                    let new_ptr = Box::into_raw(Box::new(replacement));
+
+                    // This single atomic store is what readers of `try_load` observe: they'll see
+                    // either `current_ptr` or `new_ptr`, in full, never a torn mix of the two. We
+                    // deliberately leak the retired `current_ptr` box rather than free it — a
+                    // concurrent reader may still be dereferencing it at the moment of the swap,
+                    // and this module already treats leaking a replaced recorder as an acceptable
+                    // cost (see `clear`) in exchange for never needing to prove no reader is mid-load.
+                    self.recorder.store(new_ptr, Ordering::Release);
+
+                    Ok(())
+                }
+                _ => Err(SetRecorderError(SetRecorderErrorReason::NotYetSet)),
+            };
+
+            self.upgrading.store(false, Ordering::Release);
+            outcome
         }
 
         /// Clears the currently installed recorder, allowing a new writer to override it.
@@ -102,18 +169,24 @@ mod cell {
         }
 
         pub fn try_load(&self) -> Option<&'static dyn Recorder> {
-            if self.state.load(Ordering::Acquire) != INITIALIZED {
+            if self.state.load(Ordering::Acquire) == UNINITIALIZED {
+                return None;
+            }
+
+            let ptr = self.recorder.load(Ordering::Acquire);
+            if ptr.is_null() {
                 None
             } else {
-                // SAFETY: If the state is `INITIALIZED`, then we know that the recorder has been
-                // installed and is safe to read.
-                unsafe { self.recorder.get().read() }
+                // SAFETY: a non-null pointer was produced by a prior `Box::into_raw` in `set` or
+                // `upgrade` and is never freed, so dereferencing it is always valid; the
+                // surrounding atomics guarantee we see a fully-written value, not a torn one.
+                Some(unsafe { *ptr })
             }
         }
     }
 
-    // SAFETY: We can only mutate through `set`, which is protected by the `state` and unsafe
-    // function where the caller has to guarantee synced-ness.
+    // SAFETY: We can only mutate through `set`/`upgrade`, which are protected by `state`/
+    // `upgrading` and the unsafe `clear` function where the caller has to guarantee synced-ness.
     unsafe impl Send for RecorderOnceCell {}
     unsafe impl Sync for RecorderOnceCell {}
 }
@@ -123,6 +196,12 @@ static RECORDER: RecorderOnceCell = RecorderOnceCell::new();
 static SET_RECORDER_ERROR: &str =
     "attempted to set a recorder after the metrics system was already initialized";
 
+static UPGRADE_RECORDER_NOT_SET_ERROR: &str =
+    "attempted to upgrade the recorder before one was ever installed";
+
+static UPGRADE_RECORDER_CONTENDED_ERROR: &str =
+    "attempted to upgrade the recorder while another upgrade was already in progress";
+
 /// A trait for registering and recording metrics.
 ///
 /// This is the core trait that allows interoperability between exporter implementations and the
@@ -211,6 +290,24 @@ pub fn set_boxed_recorder(recorder: Box<dyn Recorder>) -> Result<(), SetRecorder
     RECORDER.set(RecorderVariant::from_boxed(recorder))
 }
 
+/// Atomically replaces the currently installed recorder with one built from it.
+///
+/// Unlike [`clear_recorder`] followed by [`set_boxed_recorder`], this is safe to call while
+/// other threads may be loading the global recorder: `f` is handed the currently installed
+/// recorder, and the `Box<dyn Recorder>` it returns becomes the new global recorder without ever
+/// leaving readers observing an uninitialized or torn state. This is useful for long-running
+/// processes that want to layer a new recorder on top of the one installed at startup, e.g. to
+/// attach an exporter after the fact.
+///
+/// # Errors
+///
+/// An error is returned if no recorder has been installed yet.
+pub fn upgrade_recorder(
+    f: impl FnOnce(&'static dyn Recorder) -> Box<dyn Recorder>,
+) -> Result<(), SetRecorderError> {
+    RECORDER.upgrade(f)
+}
+
 /// Clears the currently configured recorder.
 ///
 /// This will leak the currently installed recorder, as we cannot safely drop it due to it being
@@ -228,19 +325,41 @@ pub unsafe fn clear_recorder() {
     RECORDER.clear();
 }
 
-/// The type returned by [`set_recorder`] if [`set_recorder`] has already been called.
+/// The type returned by [`set_recorder`] if [`set_recorder`] has already been called, or by
+/// [`upgrade_recorder`] if there was nothing installed to upgrade, or another upgrade was
+/// already in progress.
+#[derive(Debug)]
+pub struct SetRecorderError(SetRecorderErrorReason);
+
 #[derive(Debug)]
-pub struct SetRecorderError(());
+enum SetRecorderErrorReason {
+    /// `set_recorder`/`set_boxed_recorder` was called after a recorder was already installed.
+    AlreadySet,
+    /// `upgrade_recorder` was called before any recorder had been installed.
+    NotYetSet,
+    /// `upgrade_recorder` was called while another upgrade was already running.
+    UpgradeInProgress,
+}
+
+impl SetRecorderError {
+    fn message(&self) -> &'static str {
+        match self.0 {
+            SetRecorderErrorReason::AlreadySet => SET_RECORDER_ERROR,
+            SetRecorderErrorReason::NotYetSet => UPGRADE_RECORDER_NOT_SET_ERROR,
+            SetRecorderErrorReason::UpgradeInProgress => UPGRADE_RECORDER_CONTENDED_ERROR,
+        }
+    }
+}
 
 impl fmt::Display for SetRecorderError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(SET_RECORDER_ERROR)
+        fmt.write_str(self.message())
     }
 }
 
 impl std::error::Error for SetRecorderError {
     fn description(&self) -> &str {
-        SET_RECORDER_ERROR
+        self.message()
     }
 }
 
@@ -346,4 +465,79 @@ mod tests {
         assert!(second_set_result.is_err());
         assert!(was_dropped.load(Ordering::SeqCst));
     }
+
+    #[test]
+    fn upgrade_replaces_recorder_and_sees_previous_one() {
+        // Identifies itself by pushing its marker into a shared log whenever `describe_counter`
+        // is called, so the test can observe which recorder is actually installed without
+        // needing to downcast the `dyn Recorder` it gets back.
+        struct MarkerRecorder(&'static str, Arc<std::sync::Mutex<Vec<&'static str>>>);
+
+        impl Recorder for MarkerRecorder {
+            fn describe_counter(
+                &self,
+                _: crate::KeyName,
+                _: Option<crate::Unit>,
+                _: crate::SharedString,
+            ) {
+                self.1.lock().expect("log lock poisoned").push(self.0);
+            }
+            fn describe_gauge(
+                &self,
+                _: crate::KeyName,
+                _: Option<crate::Unit>,
+                _: crate::SharedString,
+            ) {
+            }
+            fn describe_histogram(
+                &self,
+                _: crate::KeyName,
+                _: Option<crate::Unit>,
+                _: crate::SharedString,
+            ) {
+            }
+
+            fn register_counter(&self, _: &crate::Key, _: &crate::Metadata<'_>) -> crate::Counter {
+                crate::Counter::noop()
+            }
+
+            fn register_gauge(&self, _: &crate::Key, _: &crate::Metadata<'_>) -> crate::Gauge {
+                crate::Gauge::noop()
+            }
+
+            fn register_histogram(
+                &self,
+                _: &crate::Key,
+                _: &crate::Metadata<'_>,
+            ) -> crate::Histogram {
+                crate::Histogram::noop()
+            }
+        }
+
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder_cell = RecorderOnceCell::new();
+
+        // Upgrading before anything has been set should fail, since there's nothing to upgrade.
+        let upgrade_before_set = recorder_cell
+            .upgrade(|_| Box::new(MarkerRecorder("unreachable", log.clone())) as Box<dyn Recorder>);
+        assert!(upgrade_before_set.is_err());
+
+        let first_set_result = recorder_cell
+            .set(RecorderVariant::from_boxed(Box::new(MarkerRecorder("first", log.clone()))));
+        assert!(first_set_result.is_ok());
+
+        // Upgrading now should succeed and hand our closure the previously installed recorder.
+        let mut saw_previous_marker = false;
+        let upgrade_result = recorder_cell.upgrade(|previous| {
+            previous.describe_counter("unused".into(), None, "unused".into());
+            saw_previous_marker = log.lock().expect("log lock poisoned").last() == Some(&"first");
+            Box::new(MarkerRecorder("second", log.clone()))
+        });
+        assert!(upgrade_result.is_ok());
+        assert!(saw_previous_marker);
+
+        let loaded = recorder_cell.try_load().expect("recorder should be loaded");
+        loaded.describe_counter("unused".into(), None, "unused".into());
+        assert_eq!(log.lock().expect("log lock poisoned").last(), Some(&"second"));
+    }
 }